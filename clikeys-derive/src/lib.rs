@@ -2,8 +2,8 @@ use proc_macro::TokenStream;
 use proc_macro2::Span;
 use quote::{quote, quote_spanned};
 use syn::{
-    Attribute, Data, DataStruct, DeriveInput, Fields, Lit, Type, parse_macro_input,
-    spanned::Spanned,
+    parse_macro_input, spanned::Spanned, Attribute, Data, DataStruct, DeriveInput, Fields, Lit,
+    Type,
 };
 
 #[proc_macro_derive(CliKeys, attributes(clikey))]
@@ -20,6 +20,8 @@ struct FieldAttr {
     help: Option<String>,
     ns: Option<String>,
     skip: bool,
+    env: Option<String>,
+    is_enum: bool,
 }
 
 fn parse_attrs(attrs: &[Attribute], field_name: &str) -> syn::Result<FieldAttr> {
@@ -28,6 +30,8 @@ fn parse_attrs(attrs: &[Attribute], field_name: &str) -> syn::Result<FieldAttr>
         help: None,
         ns: None,
         skip: false,
+        env: None,
+        is_enum: false,
     };
 
     for attr in attrs {
@@ -67,6 +71,17 @@ fn parse_attrs(attrs: &[Attribute], field_name: &str) -> syn::Result<FieldAttr>
             } else if meta.path.is_ident("skip") {
                 out.skip = true;
                 Ok(())
+            } else if meta.path.is_ident("env") {
+                let value: Lit = meta.value()?.parse()?;
+                if let Lit::Str(s) = value {
+                    out.env = Some(s.value());
+                    Ok(())
+                } else {
+                    Err(syn::Error::new(value.span(), "env must be string"))
+                }
+            } else if meta.path.is_ident("enum") {
+                out.is_enum = true;
+                Ok(())
             } else {
                 Err(meta.error("unknown attribute"))
             }
@@ -76,6 +91,33 @@ fn parse_attrs(attrs: &[Attribute], field_name: &str) -> syn::Result<FieldAttr>
     Ok(out)
 }
 
+/// Container-level `#[clikey(env_prefix = "...")]`, used to auto-derive env
+/// var names (`{PREFIX}_{FIELD}`) for leaf fields without an explicit `env`.
+fn parse_container_attrs(attrs: &[Attribute]) -> syn::Result<Option<String>> {
+    let mut env_prefix = None;
+
+    for attr in attrs {
+        if !attr.path().is_ident("clikey") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("env_prefix") {
+                let value: Lit = meta.value()?.parse()?;
+                if let Lit::Str(s) = value {
+                    env_prefix = Some(s.value());
+                    Ok(())
+                } else {
+                    Err(syn::Error::new(value.span(), "env_prefix must be string"))
+                }
+            } else {
+                Err(meta.error("unknown attribute"))
+            }
+        })?;
+    }
+
+    Ok(env_prefix)
+}
+
 fn is_leaf_type(ty: &Type) -> Option<&'static str> {
     match ty {
         Type::Path(tp) => {
@@ -97,8 +139,72 @@ fn is_leaf_type(ty: &Type) -> Option<&'static str> {
     }
 }
 
+/// If `ty` is `wrapper<T>` (e.g. `Vec<T>` or `Option<T>`), return `T`.
+fn generic_single_arg<'a>(ty: &'a Type, wrapper: &str) -> Option<&'a Type> {
+    let Type::Path(tp) = ty else {
+        return None;
+    };
+    let seg = tp.path.segments.last()?;
+    if seg.ident != wrapper {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &seg.arguments else {
+        return None;
+    };
+    match (args.args.len(), args.args.first()) {
+        (1, Some(syn::GenericArgument::Type(t))) => Some(t),
+        _ => None,
+    }
+}
+
+fn type_name_str(ty: &Type) -> String {
+    match ty {
+        Type::Path(tp) => tp
+            .path
+            .segments
+            .last()
+            .map(|s| s.ident.to_string())
+            .unwrap_or_else(|| quote!(#ty).to_string()),
+        _ => quote!(#ty).to_string(),
+    }
+}
+
+/// Build the `Option<String>` expression used to resolve a field's env var
+/// name at runtime: an explicit `#[clikey(env = "...")]` wins outright;
+/// otherwise fall back to `{EFFECTIVE_PREFIX}_{FIELD}`, where
+/// `__clikeys_effective_env_prefix` is the `Option<String>` local bound at
+/// the top of the generated function (the container's own `env_prefix`
+/// attribute, overridden by whatever an ancestor struct threads down via
+/// `options_meta_prefixed`/`apply_env_prefixed`). This is always an
+/// expression (never an empty token stream) so the generated code always
+/// has somewhere to put a potential env binding, nested or not.
+fn env_name_tokens(env: &Option<String>, field_key: &str) -> proc_macro2::TokenStream {
+    match env {
+        Some(e) => {
+            let lit = syn::LitStr::new(e, Span::call_site());
+            quote! { ::std::option::Option::Some(::std::string::String::from(#lit)) }
+        }
+        None => {
+            let upper_lit = syn::LitStr::new(&field_key.to_uppercase(), Span::call_site());
+            quote! {
+                __clikeys_effective_env_prefix
+                    .as_ref()
+                    .map(|p| ::std::format!("{p}_{}", #upper_lit))
+            }
+        }
+    }
+}
+
 fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let name = &input.ident;
+    let env_prefix = parse_container_attrs(&input.attrs)?;
+    let own_env_prefix_tokens = match &env_prefix {
+        Some(p) => {
+            let lit = syn::LitStr::new(p, Span::call_site());
+            quote! { ::std::option::Option::Some(#lit) }
+        }
+        None => quote! { ::std::option::Option::None },
+    };
 
     let ds = match &input.data {
         Data::Struct(DataStruct {
@@ -115,6 +221,7 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
 
     let mut apply_stmts = Vec::new();
     let mut meta_stmts = Vec::new();
+    let mut env_stmts = Vec::new();
 
     for f in &ds.named {
         let ident = f.ident.as_ref().unwrap();
@@ -124,6 +231,8 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
             help,
             ns,
             skip,
+            env,
+            is_enum,
         } = parse_attrs(&f.attrs, &ident.to_string())?;
 
         if skip {
@@ -133,35 +242,290 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
         let field_key = rename.unwrap_or_else(|| ident.to_string());
         let help_lit = help.unwrap_or_default();
         let fty = &f.ty;
+        let builtin_tyname = is_leaf_type(fty);
+        let is_vec_wrapper = generic_single_arg(fty, "Vec").is_some();
+        let is_option_wrapper = generic_single_arg(fty, "Option").is_some();
+        let vec_leaf =
+            generic_single_arg(fty, "Vec").and_then(|t| is_leaf_type(t).map(|tn| (t, tn)));
+        let option_leaf =
+            generic_single_arg(fty, "Option").and_then(|t| is_leaf_type(t).map(|tn| (t, tn)));
+
+        // Catch `#[clikey(enum)]` on a `Vec<T>`/`Option<T>` field up front,
+        // regardless of whether `T` itself is a recognized builtin leaf type
+        // (an enum `T` would otherwise fall through to the scalar/enum
+        // branch below with `fty` bound to the whole wrapper type, which
+        // doesn't implement `ParseFromStr`/`EnumKeys` and fails to compile
+        // with a confusing error instead of this clear one).
+        if is_enum && (is_vec_wrapper || is_option_wrapper) {
+            return Err(syn::Error::new(
+                fspan,
+                "#[clikey(enum)] is not supported on Vec<T>/Option<T> fields",
+            ));
+        }
 
-        if let Some(tyname) = is_leaf_type(fty) {
-            let tyname_str = syn::LitStr::new(tyname, Span::call_site());
+        if vec_leaf.is_none() && option_leaf.is_none() && (builtin_tyname.is_some() || is_enum) {
+            let tyname_string = builtin_tyname
+                .map(str::to_string)
+                .unwrap_or_else(|| type_name_str(fty));
+            let tyname_str = syn::LitStr::new(&tyname_string, Span::call_site());
             let key_lit = syn::LitStr::new(&field_key, Span::call_site());
 
+            // Enum leaves reject free-form errors from `ParseFromStr` in
+            // favor of an explicit list of valid variants.
+            let parse_err_for_value = if is_enum {
+                quote_spanned! {fspan=>
+                    .map_err(|_msg| ::clikeys::NsError::ParseError {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        msg: ::std::format!(
+                            "expected one of: {}",
+                            <#fty as ::clikeys::EnumKeys>::variants().join("|")
+                        ),
+                    })?
+                }
+            } else {
+                quote_spanned! {fspan=>
+                    .map_err(|msg| ::clikeys::NsError::ParseError {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                        msg,
+                    })?
+                }
+            };
+
             apply_stmts.push(quote_spanned! {fspan=>
                 if key == #key_lit {
                     let parsed = <#fty as ::clikeys::ParseFromStr>::parse_str(value)
-                        .map_err(|msg| ::clikeys::NsError::ParseError {
-                            key: key.to_string(),
-                            value: value.to_string(),
-                            msg,
-                        })?;
+                        #parse_err_for_value;
                     self.#ident = parsed;
                     return Ok(());
                 }
             });
 
+            let env_name_tokens = env_name_tokens(&env, &field_key);
+
+            let parse_err_for_val = if is_enum {
+                quote_spanned! {fspan=>
+                    .map_err(|_msg| ::clikeys::NsError::ParseError {
+                        key: #key_lit.to_string(),
+                        value: val.clone(),
+                        msg: ::std::format!(
+                            "expected one of: {}",
+                            <#fty as ::clikeys::EnumKeys>::variants().join("|")
+                        ),
+                    })?
+                }
+            } else {
+                quote_spanned! {fspan=>
+                    .map_err(|msg| ::clikeys::NsError::ParseError {
+                        key: #key_lit.to_string(),
+                        value: val.clone(),
+                        msg,
+                    })?
+                }
+            };
+
+            env_stmts.push(quote_spanned! {fspan=>
+                if let ::std::option::Option::Some(env_name) = #env_name_tokens {
+                    if let ::std::result::Result::Ok(val) = ::std::env::var(&env_name) {
+                        let parsed = <#fty as ::clikeys::ParseFromStr>::parse_str(&val)
+                            #parse_err_for_val;
+                        self.#ident = parsed;
+                    }
+                }
+            });
+
+            let meta_env_stmt = quote_spanned! {fspan=>
+                if let ::std::option::Option::Some(env_name) = #env_name_tokens {
+                    m.env = ::std::option::Option::Some(::std::borrow::Cow::Owned(env_name));
+                }
+            };
+
+            let meta_allowed_stmt = if is_enum {
+                quote_spanned! {fspan=>
+                    m.allowed = <#fty as ::clikeys::EnumKeys>::variants()
+                        .iter()
+                        .map(|v| ::std::borrow::Cow::Borrowed(*v))
+                        .collect();
+                }
+            } else {
+                quote! {}
+            };
+
+            // `#[clikey(enum)]` leaves are only required to implement
+            // `ParseFromStr` + `EnumKeys` (plus `PartialEq` for this
+            // round-trip), not `Display`/`ToString` — so render the
+            // default by finding which variant name parses back to it,
+            // rather than calling `.to_string()` on the enum value itself.
+            let default_str_expr = if is_enum {
+                quote_spanned! {fspan=>
+                    <#fty as ::clikeys::EnumKeys>::variants()
+                        .iter()
+                        .find_map(|v| {
+                            match <#fty as ::clikeys::ParseFromStr>::parse_str(v) {
+                                ::std::result::Result::Ok(parsed) if parsed == default.#ident => {
+                                    ::std::option::Option::Some((*v).to_string())
+                                }
+                                _ => ::std::option::Option::None,
+                            }
+                        })
+                        .unwrap_or_default()
+                }
+            } else {
+                quote_spanned! {fspan=> default.#ident.to_string() }
+            };
+
+            meta_stmts.push(quote_spanned! {fspan=>
+                {
+                    let mut m = ::clikeys::OptionMeta::with_default(
+                        #key_lit,
+                        #tyname_str,
+                        #help_lit,
+                        #default_str_expr
+                    );
+                    #meta_env_stmt
+                    #meta_allowed_stmt
+                    meta.push(m);
+                }
+            });
+        } else if let Some((inner_ty, inner_tyname)) = vec_leaf {
+            let key_lit = syn::LitStr::new(&field_key, Span::call_site());
+            let ty_str = syn::LitStr::new(&format!("Vec<{inner_tyname}>"), Span::call_site());
+
+            // `KEY=` (empty value) clears the vec, as the reset sentinel
+            // symmetric with `Option<T>`'s `KEY=` -> `None`. A non-empty
+            // value parses a comma-separated batch and appends it, so
+            // repeated `-o KEY=...` occurrences accumulate rather than
+            // clobber; to replace a file-provided list, override with an
+            // explicit `-o KEY=` clear followed by `-o KEY=<new values>`.
+            apply_stmts.push(quote_spanned! {fspan=>
+                if key == #key_lit {
+                    if value.is_empty() {
+                        self.#ident.clear();
+                        return Ok(());
+                    }
+                    let parsed: ::std::vec::Vec<#inner_ty> = value
+                        .split(',')
+                        .filter(|s| !s.is_empty())
+                        .map(|s| {
+                            <#inner_ty as ::clikeys::ParseFromStr>::parse_str(s).map_err(|msg| {
+                                ::clikeys::NsError::ParseError {
+                                    key: key.to_string(),
+                                    value: value.to_string(),
+                                    msg,
+                                }
+                            })
+                        })
+                        .collect::<::std::result::Result<_, _>>()?;
+                    self.#ident.extend(parsed);
+                    return Ok(());
+                }
+            });
+
+            let env_name_tokens = env_name_tokens(&env, &field_key);
+
+            env_stmts.push(quote_spanned! {fspan=>
+                if let ::std::option::Option::Some(env_name) = #env_name_tokens {
+                    if let ::std::result::Result::Ok(val) = ::std::env::var(&env_name) {
+                        let parsed: ::std::vec::Vec<#inner_ty> = val
+                            .split(',')
+                            .filter(|s| !s.is_empty())
+                            .map(|s| {
+                                <#inner_ty as ::clikeys::ParseFromStr>::parse_str(s).map_err(|msg| {
+                                    ::clikeys::NsError::ParseError {
+                                        key: #key_lit.to_string(),
+                                        value: val.clone(),
+                                        msg,
+                                    }
+                                })
+                            })
+                            .collect::<::std::result::Result<_, _>>()?;
+                        self.#ident.extend(parsed);
+                    }
+                }
+            });
+
+            let meta_env_stmt = quote_spanned! {fspan=>
+                if let ::std::option::Option::Some(env_name) = #env_name_tokens {
+                    m.env = ::std::option::Option::Some(::std::borrow::Cow::Owned(env_name));
+                }
+            };
+
+            meta_stmts.push(quote_spanned! {fspan=>
+                {
+                    let mut m = ::clikeys::OptionMeta::with_default(
+                        #key_lit,
+                        #ty_str,
+                        #help_lit,
+                        ::clikeys::stringify_vec(&default.#ident)
+                    );
+                    #meta_env_stmt
+                    meta.push(m);
+                }
+            });
+        } else if let Some((inner_ty, inner_tyname)) = option_leaf {
+            let key_lit = syn::LitStr::new(&field_key, Span::call_site());
+            let ty_str = syn::LitStr::new(&format!("Option<{inner_tyname}>"), Span::call_site());
+
+            apply_stmts.push(quote_spanned! {fspan=>
+                if key == #key_lit {
+                    self.#ident = if value.is_empty() {
+                        ::std::option::Option::None
+                    } else {
+                        let parsed = <#inner_ty as ::clikeys::ParseFromStr>::parse_str(value)
+                            .map_err(|msg| ::clikeys::NsError::ParseError {
+                                key: key.to_string(),
+                                value: value.to_string(),
+                                msg,
+                            })?;
+                        ::std::option::Option::Some(parsed)
+                    };
+                    return Ok(());
+                }
+            });
+
+            let env_name_tokens = env_name_tokens(&env, &field_key);
+
+            env_stmts.push(quote_spanned! {fspan=>
+                if let ::std::option::Option::Some(env_name) = #env_name_tokens {
+                    if let ::std::result::Result::Ok(val) = ::std::env::var(&env_name) {
+                        self.#ident = if val.is_empty() {
+                            ::std::option::Option::None
+                        } else {
+                            let parsed = <#inner_ty as ::clikeys::ParseFromStr>::parse_str(&val)
+                                .map_err(|msg| ::clikeys::NsError::ParseError {
+                                    key: #key_lit.to_string(),
+                                    value: val.clone(),
+                                    msg,
+                                })?;
+                            ::std::option::Option::Some(parsed)
+                        };
+                    }
+                }
+            });
+
+            let meta_env_stmt = quote_spanned! {fspan=>
+                if let ::std::option::Option::Some(env_name) = #env_name_tokens {
+                    m.env = ::std::option::Option::Some(::std::borrow::Cow::Owned(env_name));
+                }
+            };
+
             meta_stmts.push(quote_spanned! {fspan=>
-                meta.push(::clikeys::OptionMeta::with_default(
-                    #key_lit,
-                    #tyname_str,
-                    #help_lit,
-                    default.#ident.to_string()
-                ));
+                {
+                    let mut m = ::clikeys::OptionMeta::with_default(
+                        #key_lit,
+                        #ty_str,
+                        #help_lit,
+                        ::clikeys::stringify_option(&default.#ident)
+                    );
+                    #meta_env_stmt
+                    meta.push(m);
+                }
             });
         } else {
             let ns_str = ns.unwrap_or_else(|| field_key.clone());
             let ns_lit = syn::LitStr::new(&ns_str, Span::call_site());
+            let ns_upper_lit = syn::LitStr::new(&ns_str.to_uppercase(), Span::call_site());
 
             apply_stmts.push(quote_spanned! {fspan=>
                 if let Some((seg, rest)) = ::clikeys::split_once(key, '.') {
@@ -173,11 +537,27 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
 
             meta_stmts.push(quote_spanned! {fspan=>
                 {
-                    let child = <#fty as ::clikeys::CliKeys>::options_meta();
+                    let child_prefix: ::std::option::Option<::std::string::String> =
+                        __clikeys_effective_env_prefix
+                            .as_ref()
+                            .map(|p| ::std::format!("{p}_{}", #ns_upper_lit));
+                    let child = <#fty as ::clikeys::CliKeys>::options_meta_prefixed(
+                        child_prefix.as_deref(),
+                    );
                     let child = ::clikeys::prefix_meta(#ns_lit, child);
                     meta.extend(child);
                 }
             });
+
+            env_stmts.push(quote_spanned! {fspan=>
+                {
+                    let child_prefix: ::std::option::Option<::std::string::String> =
+                        __clikeys_effective_env_prefix
+                            .as_ref()
+                            .map(|p| ::std::format!("{p}_{}", #ns_upper_lit));
+                    ::clikeys::CliKeys::apply_env_prefixed(&mut self.#ident, child_prefix.as_deref())?;
+                }
+            });
         }
     }
 
@@ -188,7 +568,17 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let tokens = quote! {
         impl ::clikeys::CliKeys for #name {
             fn options_meta() -> ::std::vec::Vec<::clikeys::OptionMeta> {
+                <Self as ::clikeys::CliKeys>::options_meta_prefixed(::std::option::Option::None)
+            }
+
+            fn options_meta_prefixed(
+                env_prefix: ::std::option::Option<&str>,
+            ) -> ::std::vec::Vec<::clikeys::OptionMeta> {
                 let default: Self = <Self as ::std::default::Default>::default();
+                let __clikeys_effective_env_prefix: ::std::option::Option<::std::string::String> =
+                    env_prefix
+                        .map(|p| p.to_string())
+                        .or_else(|| (#own_env_prefix_tokens).map(|s: &str| s.to_string()));
                 let mut meta = ::std::vec::Vec::new();
                 #(#meta_stmts)*
                 meta
@@ -199,6 +589,22 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
             {
                 #(#apply_stmts)*
             }
+
+            fn apply_env(&mut self) -> ::std::result::Result<(), ::clikeys::NsError> {
+                <Self as ::clikeys::CliKeys>::apply_env_prefixed(self, ::std::option::Option::None)
+            }
+
+            fn apply_env_prefixed(
+                &mut self,
+                env_prefix: ::std::option::Option<&str>,
+            ) -> ::std::result::Result<(), ::clikeys::NsError> {
+                let __clikeys_effective_env_prefix: ::std::option::Option<::std::string::String> =
+                    env_prefix
+                        .map(|p| p.to_string())
+                        .or_else(|| (#own_env_prefix_tokens).map(|s: &str| s.to_string()));
+                #(#env_stmts)*
+                Ok(())
+            }
         }
 
         impl #name {
@@ -218,7 +624,69 @@ fn impl_clikeys(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
                             msg: ::std::string::String::from("expected KEY=VALUE"),
                         });
                     };
-                    ::clikeys::CliKeys::apply_kv(&mut cfg, key, value)?;
+                    if let Err(err) = ::clikeys::CliKeys::apply_kv(&mut cfg, key, value) {
+                        return Err(::clikeys::enrich_unknown_key(err, key, &Self::options_meta()));
+                    }
+                }
+                Ok(cfg)
+            }
+
+            pub fn new_from_env_and_options<I, S>(options: I)
+                -> ::std::result::Result<Self, ::clikeys::NsError>
+            where
+                I: ::std::iter::IntoIterator<Item = S>,
+                S: ::std::convert::AsRef<str>,
+            {
+                let mut cfg: Self = ::std::default::Default::default();
+                ::clikeys::CliKeys::apply_env(&mut cfg)?;
+                for opt in options {
+                    let opt = opt.as_ref();
+                    let Some((key, value)) = ::clikeys::split_once(opt, '=') else {
+                        return Err(::clikeys::NsError::ParseError {
+                            key: opt.to_string(),
+                            value: ::std::string::String::new(),
+                            msg: ::std::string::String::from("expected KEY=VALUE"),
+                        });
+                    };
+                    if let Err(err) = ::clikeys::CliKeys::apply_kv(&mut cfg, key, value) {
+                        return Err(::clikeys::enrich_unknown_key(err, key, &Self::options_meta()));
+                    }
+                }
+                Ok(cfg)
+            }
+
+            pub fn from_config_file<P>(path: P) -> ::std::result::Result<Self, ::clikeys::ConfigError>
+            where
+                P: ::std::convert::AsRef<::std::path::Path>,
+            {
+                let mut cfg: Self = ::std::default::Default::default();
+                ::clikeys::apply_config_file(&mut cfg, path.as_ref())?;
+                Ok(cfg)
+            }
+
+            pub fn new_with_file_and_options<P, I, S>(
+                path: P,
+                overrides: I,
+            ) -> ::std::result::Result<Self, ::clikeys::ConfigError>
+            where
+                P: ::std::convert::AsRef<::std::path::Path>,
+                I: ::std::iter::IntoIterator<Item = S>,
+                S: ::std::convert::AsRef<str>,
+            {
+                let mut cfg = Self::from_config_file(path)?;
+                for opt in overrides {
+                    let opt = opt.as_ref();
+                    let Some((key, value)) = ::clikeys::split_once(opt, '=') else {
+                        return Err(::clikeys::NsError::ParseError {
+                            key: opt.to_string(),
+                            value: ::std::string::String::new(),
+                            msg: ::std::string::String::from("expected KEY=VALUE"),
+                        }
+                        .into());
+                    };
+                    if let Err(err) = ::clikeys::CliKeys::apply_kv(&mut cfg, key, value) {
+                        return Err(::clikeys::enrich_unknown_key(err, key, &Self::options_meta()).into());
+                    }
                 }
                 Ok(cfg)
             }
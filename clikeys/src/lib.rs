@@ -1,4 +1,12 @@
-use std::{borrow::Cow, str::FromStr};
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+/// Re-exported so callers only need `use clikeys::CliKeys;` to bring both
+/// the trait and the `#[derive(CliKeys)]` macro into scope.
+pub use clikeys_derive::CliKeys;
 
 #[derive(Debug, thiserror::Error)]
 pub enum NsError {
@@ -22,6 +30,10 @@ pub struct OptionMeta {
     pub help: Cow<'static, str>,
     /// Stringified default.
     pub default: String,
+    /// Environment variable this key is bound to, if any.
+    pub env: Option<Cow<'static, str>>,
+    /// Valid variants for `#[clikey(enum)]` fields; empty for non-enum keys.
+    pub allowed: Vec<Cow<'static, str>>,
 }
 
 impl OptionMeta {
@@ -36,26 +48,75 @@ impl OptionMeta {
             ty: ty.into(),
             help: help.into(),
             default,
+            env: None,
+            allowed: Vec::new(),
         }
     }
 }
 
+/// Implemented by `#[clikey(enum)]` leaf types to expose their valid
+/// variants for validation errors and help/schema output.
+pub trait EnumKeys {
+    fn variants() -> &'static [&'static str];
+}
+
 pub trait CliKeys: Sized + Default {
     /// Return all options (fully qualified keys for this *type* in isolation).
     ///
     /// For nested fields, parents will prefix child keys (e.g., "backend.*").
     fn options_meta() -> Vec<OptionMeta>;
 
+    /// Like [`Self::options_meta`], but `env_prefix` (when given) is threaded
+    /// down from an ancestor struct's `#[clikey(env_prefix = "...")]` so a
+    /// nested field without its own explicit `env` still gets an
+    /// auto-derived name reflecting the full dotted key (e.g.
+    /// `backend.d_model` under prefix `APP` becomes `APP_BACKEND_D_MODEL`
+    /// rather than just `APP_D_MODEL`).
+    ///
+    /// Manual implementations that don't need prefix-aware nesting can rely
+    /// on the default, which ignores `env_prefix` and falls back to
+    /// [`Self::options_meta`].
+    fn options_meta_prefixed(_env_prefix: Option<&str>) -> Vec<OptionMeta> {
+        Self::options_meta()
+    }
+
     /// Pretty-printed help table, grouped by first path segment.
     fn options_help() -> String {
         format_options_help(&Self::options_meta())
     }
 
+    /// Machine-readable form of [`Self::options_meta`] for tooling (e.g. a
+    /// wrapper CLI or editor plugin discovering configurable keys).
+    fn options_schema() -> serde_json::Value {
+        options_schema_json(&Self::options_meta())
+    }
+
+    /// Shell completion fragment enumerating the dotted keys.
+    fn print_completions(shell: Shell) -> String {
+        format_completions(shell, &Self::options_meta())
+    }
+
     /// Apply a single `key=value` override inside this *local* namespace.
     ///
     /// Parents pass the remainder after stripping their prefix, or pass an
     /// already fully-qualified key if this is a root config.
     fn apply_kv(&mut self, key: &str, value: &str) -> Result<(), NsError>;
+
+    /// Populate fields bound to an environment variable (via
+    /// `#[clikey(env = "...")]` or an auto-derived name) from the process
+    /// environment, leaving unset variables at their current value.
+    fn apply_env(&mut self) -> Result<(), NsError> {
+        Ok(())
+    }
+
+    /// Like [`Self::apply_env`], but threads an inherited env-var prefix down
+    /// to nested fields the same way [`Self::options_meta_prefixed`] does.
+    ///
+    /// Manual implementations can rely on the default, which ignores
+    /// `env_prefix` and falls back to [`Self::apply_env`].
+    fn apply_env_prefixed(&mut self, _env_prefix: Option<&str>) -> Result<(), NsError> {
+        self.apply_env()
+    }
 }
 
 pub fn format_options_help(options: &[OptionMeta]) -> String {
@@ -81,9 +142,18 @@ pub fn format_options_help(options: &[OptionMeta]) -> String {
             let key = &m.key;
             let ty = &m.ty;
             let help = if m.help.is_empty() { "" } else { &m.help };
+            let env = match &m.env {
+                Some(name) => format!(" [env: {name}]"),
+                None => String::new(),
+            };
+            let allowed = if m.allowed.is_empty() {
+                String::new()
+            } else {
+                format!(" (one of: {})", m.allowed.iter().join("|"))
+            };
             lines.push(format!(
-                "  {:<28}  default = {:<10}  ({}) {}",
-                key, m.default, ty, help
+                "  {:<28}  default = {:<10}  ({}) {}{}{}",
+                key, m.default, ty, help, env, allowed
             ));
         }
     }
@@ -91,6 +161,140 @@ pub fn format_options_help(options: &[OptionMeta]) -> String {
     lines.join("\n")
 }
 
+// ------ machine-readable schema export and shell completions ------
+
+/// Serialize `options` to the JSON schema returned by
+/// [`CliKeys::options_schema`].
+pub fn options_schema_json(options: &[OptionMeta]) -> serde_json::Value {
+    serde_json::Value::Array(
+        options
+            .iter()
+            .map(|m| {
+                serde_json::json!({
+                    "key": m.key,
+                    "ty": m.ty,
+                    "help": m.help,
+                    "default": m.default,
+                    "env": m.env,
+                    "allowed": m.allowed,
+                    "repeatable": m.ty.starts_with("Vec<"),
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Shell targeted by [`CliKeys::print_completions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+}
+
+/// Render a completion fragment enumerating `options`' dotted keys.
+pub fn format_completions(shell: Shell, options: &[OptionMeta]) -> String {
+    let keys: Vec<String> = options.iter().map(|m| format!("{}=", m.key)).collect();
+    let word_list = keys.join(" ");
+
+    match shell {
+        Shell::Bash => format!(
+            "_clikeys_options() {{\n    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    COMPREPLY=( $(compgen -W \"{word_list}\" -- \"$cur\") )\n}}\ncomplete -F _clikeys_options -o nospace\n"
+        ),
+        Shell::Zsh => format!("#compdef -\n_arguments '*: :({word_list})'\n"),
+    }
+}
+
+// ------ stringification helpers for repeatable/optional leaves ------
+
+/// Render a `Vec<T>` default as a comma-joined list, as accepted by the
+/// generated `apply_kv` for repeatable (`Vec<T>`) keys.
+pub fn stringify_vec<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(T::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Render an `Option<T>` default; `None` prints as the empty string, which
+/// is also what the generated `apply_kv` treats as `None` on input.
+pub fn stringify_option<T: ToString>(value: &Option<T>) -> String {
+    value.as_ref().map(T::to_string).unwrap_or_default()
+}
+
+// ------ config-file layer (derive-generated `from_config_file` delegates here) ------
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {source}")]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    #[error("failed to parse config file {path}: {source}")]
+    Toml {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error(transparent)]
+    Ns(#[from] NsError),
+}
+
+/// Read `path` as a TOML document, flatten it into dotted `key=value` pairs,
+/// and apply each one through `cfg`'s [`CliKeys::apply_kv`].
+pub fn apply_config_file<T: CliKeys>(cfg: &mut T, path: &Path) -> Result<(), ConfigError> {
+    let text = std::fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let value: toml::Value = toml::from_str(&text).map_err(|source| ConfigError::Toml {
+        path: path.to_path_buf(),
+        source,
+    })?;
+
+    let mut flat = Vec::new();
+    flatten_toml_table("", &value, &mut flat);
+
+    for (key, value) in flat {
+        cfg.apply_kv(&key, &value)
+            .map_err(|err| enrich_unknown_key(err, &key, &T::options_meta()))?;
+    }
+
+    Ok(())
+}
+
+fn flatten_toml_table(prefix: &str, value: &toml::Value, out: &mut Vec<(String, String)>) {
+    match value {
+        toml::Value::Table(table) => {
+            for (k, v) in table {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{prefix}.{k}")
+                };
+                flatten_toml_table(&key, v, out);
+            }
+        }
+        other => out.push((prefix.to_string(), toml_scalar_to_string(other))),
+    }
+}
+
+fn toml_scalar_to_string(value: &toml::Value) -> String {
+    match value {
+        toml::Value::String(s) => s.clone(),
+        toml::Value::Integer(i) => i.to_string(),
+        toml::Value::Float(f) => f.to_string(),
+        toml::Value::Boolean(b) => b.to_string(),
+        toml::Value::Datetime(dt) => dt.to_string(),
+        toml::Value::Array(items) => items
+            .iter()
+            .map(toml_scalar_to_string)
+            .collect::<Vec<_>>()
+            .join(","),
+        toml::Value::Table(_) => unreachable!("tables are flattened before reaching a scalar"),
+    }
+}
+
 // ------ value parsing helpers used by the derive macro ------
 
 pub trait ParseFromStr: Sized {
@@ -138,3 +342,352 @@ pub fn split_once(s: &str, delim: char) -> Option<(&str, &str)> {
     let idx = s.find(delim)?;
     Some((&s[..idx], &s[idx + 1..]))
 }
+
+// ------ "did you mean ...?" suggestions for unknown keys ------
+
+/// Levenshtein edit distance between `a` and `b`.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![0usize; b_chars.len() + 1];
+        cur[0] = i + 1;
+        for (j, &cb) in b_chars.iter().enumerate() {
+            let cost = if ca == cb { 0 } else { 1 };
+            cur[j + 1] = (prev[j + 1] + 1).min(cur[j] + 1).min(prev[j] + cost);
+        }
+        prev = cur;
+    }
+
+    prev[b_chars.len()]
+}
+
+/// Find the closest key to `key` among `candidates`, or `None` if nothing is
+/// close enough to be a plausible typo.
+pub fn suggest_key<'a, I>(key: &str, candidates: I) -> Option<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let threshold = (key.len() / 3).max(2);
+    candidates
+        .into_iter()
+        .map(|candidate| (levenshtein(key, candidate), candidate))
+        .filter(|(dist, _)| *dist <= threshold)
+        .min_by_key(|(dist, _)| *dist)
+        .map(|(_, candidate)| candidate)
+}
+
+/// If `err` is an [`NsError::UnknownKey`], rewrite it to include a "did you
+/// mean ...?" hint computed against `meta`; otherwise pass it through as-is.
+pub fn enrich_unknown_key(err: NsError, key: &str, meta: &[OptionMeta]) -> NsError {
+    match err {
+        NsError::UnknownKey(_) => {
+            let candidates = meta.iter().map(|m| m.key.as_ref());
+            match suggest_key(key, candidates) {
+                Some(suggestion) => {
+                    NsError::UnknownKey(format!("{key} (did you mean '{suggestion}'?)"))
+                }
+                None => NsError::UnknownKey(key.to_string()),
+            }
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levenshtein_identical_strings() {
+        assert_eq!(levenshtein("backend", "backend"), 0);
+    }
+
+    #[test]
+    fn levenshtein_counts_substitution_insertion_deletion() {
+        assert_eq!(levenshtein("kitten", "sitting"), 3);
+        assert_eq!(levenshtein("d_model", "d_models"), 1);
+        assert_eq!(levenshtein("d_model", "d_mode"), 1);
+    }
+
+    #[test]
+    fn levenshtein_against_empty_string_is_length() {
+        assert_eq!(levenshtein("", "abc"), 3);
+        assert_eq!(levenshtein("abc", ""), 3);
+    }
+
+    #[test]
+    fn suggest_key_finds_close_typo() {
+        let candidates = ["backend.d_model", "backend.n_layers", "trainer.lr"];
+        assert_eq!(
+            suggest_key("backend.d_modle", candidates.into_iter()),
+            Some("backend.d_model")
+        );
+    }
+
+    #[test]
+    fn suggest_key_respects_threshold_for_short_keys() {
+        // threshold = max(2, len/3) of the queried key; "l" (len 1) has
+        // threshold 2, so "lr" at distance 1 should still match.
+        let candidates = ["lr"];
+        assert_eq!(suggest_key("l", candidates.into_iter()), Some("lr"));
+    }
+
+    #[test]
+    fn suggest_key_none_when_nothing_close_enough() {
+        let candidates = ["backend.d_model", "trainer.lr"];
+        assert_eq!(
+            suggest_key("completely_unrelated", candidates.into_iter()),
+            None
+        );
+    }
+
+    #[test]
+    fn suggest_key_picks_closest_of_several() {
+        let candidates = ["backend.d_model", "backend.d_models", "backend.n_layers"];
+        // "backend.d_model" is an exact match (distance 0), so it should win
+        // over the merely-close "backend.d_models".
+        assert_eq!(
+            suggest_key("backend.d_model", candidates.into_iter()),
+            Some("backend.d_model")
+        );
+    }
+
+    #[test]
+    fn enrich_unknown_key_adds_suggestion() {
+        let meta = vec![OptionMeta::with_default(
+            "backend.d_model",
+            "usize",
+            "",
+            "512".to_string(),
+        )];
+        let err = enrich_unknown_key(
+            NsError::UnknownKey("backend.d_modle".to_string()),
+            "backend.d_modle",
+            &meta,
+        );
+        match err {
+            NsError::UnknownKey(msg) => {
+                assert!(msg.contains("did you mean 'backend.d_model'?"), "{msg}");
+            }
+            other => panic!("expected UnknownKey, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn enrich_unknown_key_leaves_other_errors_untouched() {
+        let err = NsError::ParseError {
+            key: "backend.d_model".to_string(),
+            value: "x".to_string(),
+            msg: "invalid digit".to_string(),
+        };
+        let enriched = enrich_unknown_key(err, "backend.d_model", &[]);
+        assert!(matches!(enriched, NsError::ParseError { .. }));
+    }
+
+    #[test]
+    fn flatten_toml_table_handles_nested_tables_and_arrays() {
+        let toml_text = r#"
+            [backend]
+            d_model = 512
+            devices = [0, 1, 2]
+
+            [trainer]
+            lr = 0.001
+        "#;
+        let value: toml::Value = toml::from_str(toml_text).unwrap();
+        let mut flat = Vec::new();
+        flatten_toml_table("", &value, &mut flat);
+        flat.sort();
+
+        assert_eq!(
+            flat,
+            vec![
+                ("backend.d_model".to_string(), "512".to_string()),
+                ("backend.devices".to_string(), "0,1,2".to_string()),
+                ("trainer.lr".to_string(), "0.001".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn toml_scalar_to_string_renders_each_variant() {
+        assert_eq!(
+            toml_scalar_to_string(&toml::Value::String("hi".to_string())),
+            "hi"
+        );
+        assert_eq!(toml_scalar_to_string(&toml::Value::Integer(42)), "42");
+        assert_eq!(toml_scalar_to_string(&toml::Value::Boolean(true)), "true");
+        assert_eq!(
+            toml_scalar_to_string(&toml::Value::Array(vec![
+                toml::Value::Integer(1),
+                toml::Value::Integer(2),
+            ])),
+            "1,2"
+        );
+    }
+
+    #[test]
+    fn apply_config_file_applies_flattened_keys() {
+        #[derive(Default)]
+        struct Root {
+            d_model: usize,
+            lr: f64,
+        }
+
+        impl CliKeys for Root {
+            fn options_meta() -> Vec<OptionMeta> {
+                vec![
+                    OptionMeta::with_default("d_model", "usize", "", "0".to_string()),
+                    OptionMeta::with_default("lr", "f64", "", "0".to_string()),
+                ]
+            }
+
+            fn apply_kv(&mut self, key: &str, value: &str) -> Result<(), NsError> {
+                match key {
+                    "d_model" => {
+                        self.d_model = value.parse().map_err(|e: std::num::ParseIntError| {
+                            NsError::ParseError {
+                                key: key.to_string(),
+                                value: value.to_string(),
+                                msg: e.to_string(),
+                            }
+                        })?;
+                        Ok(())
+                    }
+                    "lr" => {
+                        self.lr = value.parse().map_err(|e: std::num::ParseFloatError| {
+                            NsError::ParseError {
+                                key: key.to_string(),
+                                value: value.to_string(),
+                                msg: e.to_string(),
+                            }
+                        })?;
+                        Ok(())
+                    }
+                    other => Err(NsError::UnknownKey(other.to_string())),
+                }
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "clikeys-apply-config-file-test-{:?}.toml",
+            std::thread::current().id()
+        ));
+        std::fs::write(&path, "d_model = 768\nlr = 0.01\n").unwrap();
+
+        let mut cfg = Root::default();
+        apply_config_file(&mut cfg, &path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(cfg.d_model, 768);
+        assert_eq!(cfg.lr, 0.01);
+    }
+
+    #[test]
+    fn apply_config_file_enriches_unknown_key_errors() {
+        #[derive(Default)]
+        struct Root {
+            d_model: usize,
+        }
+
+        impl CliKeys for Root {
+            fn options_meta() -> Vec<OptionMeta> {
+                vec![OptionMeta::with_default(
+                    "d_model",
+                    "usize",
+                    "",
+                    "0".to_string(),
+                )]
+            }
+
+            fn apply_kv(&mut self, key: &str, value: &str) -> Result<(), NsError> {
+                if key == "d_model" {
+                    self.d_model = value.parse().unwrap();
+                    Ok(())
+                } else {
+                    Err(NsError::UnknownKey(key.to_string()))
+                }
+            }
+        }
+
+        let path = std::env::temp_dir().join(format!(
+            "clikeys-apply-config-file-test-unknown-{:?}.toml",
+            std::thread::current().id()
+        ));
+        std::fs::write(&path, "d_modle = 768\n").unwrap();
+
+        let mut cfg = Root::default();
+        let err = apply_config_file(&mut cfg, &path).unwrap_err();
+        std::fs::remove_file(&path).ok();
+
+        match err {
+            ConfigError::Ns(NsError::UnknownKey(msg)) => {
+                assert!(msg.contains("did you mean 'd_model'?"), "{msg}");
+            }
+            other => panic!("expected ConfigError::Ns(UnknownKey), got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stringify_vec_joins_with_commas() {
+        assert_eq!(stringify_vec::<u32>(&[]), "");
+        assert_eq!(stringify_vec(&[1u32, 2, 3]), "1,2,3");
+    }
+
+    #[test]
+    fn stringify_option_renders_empty_for_none() {
+        assert_eq!(stringify_option::<u32>(&None), "");
+        assert_eq!(stringify_option(&Some(7u32)), "7");
+    }
+
+    #[test]
+    fn options_schema_json_shape() {
+        let mut meta =
+            OptionMeta::with_default("backend.devices", "Vec<u32>", "", "0,1".to_string());
+        meta.env = Some(Cow::Borrowed("APP_BACKEND_DEVICES"));
+        meta.allowed = vec![Cow::Borrowed("a"), Cow::Borrowed("b")];
+
+        let schema = options_schema_json(&[meta]);
+        let entry = &schema[0];
+        assert_eq!(entry["key"], "backend.devices");
+        assert_eq!(entry["ty"], "Vec<u32>");
+        assert_eq!(entry["default"], "0,1");
+        assert_eq!(entry["env"], "APP_BACKEND_DEVICES");
+        assert_eq!(entry["allowed"], serde_json::json!(["a", "b"]));
+        assert_eq!(entry["repeatable"], true);
+    }
+
+    #[test]
+    fn options_schema_json_repeatable_false_for_scalar() {
+        let meta = OptionMeta::with_default("trainer.lr", "f64", "", "0.001".to_string());
+        let schema = options_schema_json(&[meta]);
+        assert_eq!(schema[0]["repeatable"], false);
+    }
+
+    #[test]
+    fn format_completions_bash_lists_keys_with_trailing_equals() {
+        let meta = vec![
+            OptionMeta::with_default("backend.d_model", "usize", "", "512".to_string()),
+            OptionMeta::with_default("trainer.lr", "f64", "", "0.001".to_string()),
+        ];
+        let out = format_completions(Shell::Bash, &meta);
+        assert!(out.contains("backend.d_model="));
+        assert!(out.contains("trainer.lr="));
+        assert!(out.contains("complete -F _clikeys_options"));
+    }
+
+    #[test]
+    fn format_completions_zsh_lists_keys_with_trailing_equals() {
+        let meta = vec![OptionMeta::with_default(
+            "backend.d_model",
+            "usize",
+            "",
+            "512".to_string(),
+        )];
+        let out = format_completions(Shell::Zsh, &meta);
+        assert!(out.contains("backend.d_model="));
+        assert!(out.starts_with("#compdef"));
+    }
+}
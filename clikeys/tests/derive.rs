@@ -0,0 +1,190 @@
+//! Exercises `#[derive(CliKeys)]` end-to-end on a struct combining a nested
+//! namespace, an enum leaf, a `Vec<T>` leaf and an `Option<T>` leaf.
+
+use clikeys::{CliKeys, EnumKeys, NsError, ParseFromStr};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Precision {
+    Fp32,
+    Fp16,
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Precision::Fp32
+    }
+}
+
+impl ParseFromStr for Precision {
+    fn parse_str(s: &str) -> Result<Self, String> {
+        match s {
+            "fp32" => Ok(Precision::Fp32),
+            "fp16" => Ok(Precision::Fp16),
+            other => Err(format!("unknown precision: {other}")),
+        }
+    }
+}
+
+impl EnumKeys for Precision {
+    fn variants() -> &'static [&'static str] {
+        &["fp32", "fp16"]
+    }
+}
+
+#[derive(Debug, Default, CliKeys)]
+struct Backend {
+    #[clikey(help = "hidden size")]
+    d_model: usize,
+    #[clikey(enum)]
+    precision: Precision,
+}
+
+#[derive(Debug, Default, CliKeys)]
+#[clikey(env_prefix = "CLIKEYS_TEST")]
+struct Root {
+    #[clikey(ns)]
+    backend: Backend,
+    #[clikey(help = "device ids to shard across")]
+    devices: Vec<u32>,
+    #[clikey(env = "CLIKEYS_TEST_CHECKPOINT")]
+    checkpoint: Option<String>,
+}
+
+#[test]
+fn nested_namespace_routes_to_child_field() {
+    let mut root = Root::default();
+    root.apply_kv("backend.d_model", "768").unwrap();
+    assert_eq!(root.backend.d_model, 768);
+}
+
+#[test]
+fn enum_leaf_accepts_valid_variant_and_rejects_others() {
+    let mut root = Root::default();
+    root.apply_kv("backend.precision", "fp16").unwrap();
+    assert_eq!(root.backend.precision, Precision::Fp16);
+
+    let err = root.apply_kv("backend.precision", "bf16").unwrap_err();
+    match err {
+        NsError::ParseError { msg, .. } => {
+            assert!(msg.contains("fp32"), "{msg}");
+            assert!(msg.contains("fp16"), "{msg}");
+        }
+        other => panic!("expected ParseError, got {other:?}"),
+    }
+}
+
+#[test]
+fn vec_leaf_appends_on_write_and_clears_on_empty_value() {
+    let mut root = Root::default();
+    root.apply_kv("devices", "0,1").unwrap();
+    root.apply_kv("devices", "2").unwrap();
+    assert_eq!(root.devices, vec![0, 1, 2]);
+
+    root.apply_kv("devices", "").unwrap();
+    assert!(root.devices.is_empty());
+}
+
+#[test]
+fn option_leaf_treats_empty_value_as_none() {
+    let mut root = Root::default();
+    root.apply_kv("checkpoint", "ckpt.pt").unwrap();
+    assert_eq!(root.checkpoint, Some("ckpt.pt".to_string()));
+
+    root.apply_kv("checkpoint", "").unwrap();
+    assert_eq!(root.checkpoint, None);
+}
+
+#[test]
+fn unknown_key_reports_unknown_key_error() {
+    let mut root = Root::default();
+    let err = root.apply_kv("backend.d_modle", "1").unwrap_err();
+    assert!(matches!(err, NsError::UnknownKey(_)));
+}
+
+#[test]
+fn env_applies_before_explicit_overrides_win() {
+    // SAFETY (test-only): unique env var name avoids cross-test interference.
+    std::env::set_var("CLIKEYS_TEST_CHECKPOINT", "from-env.pt");
+    std::env::set_var("CLIKEYS_TEST_DEVICES", "5,6");
+
+    let mut root = Root::default();
+    root.apply_env().unwrap();
+    assert_eq!(root.checkpoint, Some("from-env.pt".to_string()));
+    assert_eq!(root.devices, vec![5, 6]);
+
+    // An explicit override still wins over the env-applied value.
+    root.apply_kv("checkpoint", "from-override.pt").unwrap();
+    assert_eq!(root.checkpoint, Some("from-override.pt".to_string()));
+
+    std::env::remove_var("CLIKEYS_TEST_CHECKPOINT");
+    std::env::remove_var("CLIKEYS_TEST_DEVICES");
+}
+
+#[test]
+fn options_meta_prefixes_nested_keys_and_records_env_and_allowed() {
+    let meta = Root::options_meta();
+
+    let d_model = meta
+        .iter()
+        .find(|m| m.key == "backend.d_model")
+        .expect("backend.d_model present");
+    assert_eq!(d_model.ty, "usize");
+
+    let precision = meta
+        .iter()
+        .find(|m| m.key == "backend.precision")
+        .expect("backend.precision present");
+    assert_eq!(precision.allowed.len(), 2);
+
+    let devices = meta
+        .iter()
+        .find(|m| m.key == "devices")
+        .expect("devices present");
+    assert_eq!(devices.ty, "Vec<u32>");
+    assert_eq!(devices.env.as_deref(), Some("CLIKEYS_TEST_DEVICES"));
+
+    let checkpoint = meta
+        .iter()
+        .find(|m| m.key == "checkpoint")
+        .expect("checkpoint present");
+    assert_eq!(checkpoint.ty, "Option<String>");
+    assert_eq!(checkpoint.env.as_deref(), Some("CLIKEYS_TEST_CHECKPOINT"));
+}
+
+#[test]
+fn root_env_prefix_threads_through_nested_namespace() {
+    // `backend.d_model` has no explicit `env`, so it must inherit `Root`'s
+    // `env_prefix` plus the `backend` namespace segment rather than being
+    // left unset.
+    let meta = Root::options_meta();
+    let d_model = meta
+        .iter()
+        .find(|m| m.key == "backend.d_model")
+        .expect("backend.d_model present");
+    assert_eq!(d_model.env.as_deref(), Some("CLIKEYS_TEST_BACKEND_D_MODEL"));
+
+    // SAFETY (test-only): unique env var name avoids cross-test interference.
+    std::env::set_var("CLIKEYS_TEST_BACKEND_D_MODEL", "1024");
+    let mut root = Root::default();
+    root.apply_env().unwrap();
+    assert_eq!(root.backend.d_model, 1024);
+    std::env::remove_var("CLIKEYS_TEST_BACKEND_D_MODEL");
+}
+
+#[test]
+fn options_schema_reflects_repeatable_flag() {
+    let schema = Root::options_schema();
+    let entries = schema.as_array().unwrap();
+
+    let devices = entries
+        .iter()
+        .find(|e| e["key"] == "devices")
+        .expect("devices entry present");
+    assert_eq!(devices["repeatable"], true);
+
+    let checkpoint = entries
+        .iter()
+        .find(|e| e["key"] == "checkpoint")
+        .expect("checkpoint entry present");
+    assert_eq!(checkpoint["repeatable"], false);
+}